@@ -0,0 +1,115 @@
+// Thin SQLite wrapper for persisted expansion rules. Kept intentionally
+// small (no ORM, no query builder) in the spirit of a lightweight
+// sqlez-style connection type: one struct, one migration runner keyed on
+// `PRAGMA user_version`, and a handful of CRUD methods the Tauri commands
+// call directly.
+use rusqlite::{params, Connection};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Wry};
+
+#[derive(Debug, Clone)]
+pub struct RuleRecord {
+    pub id: i64,
+    pub command: String,
+    pub replacement: String,
+    pub enabled: bool,
+    pub created_at: String,
+}
+
+pub struct RuleStore {
+    conn: Mutex<Option<Connection>>,
+}
+
+impl RuleStore {
+    pub fn new() -> Self {
+        Self { conn: Mutex::new(None) }
+    }
+
+    /// Opens (creating if needed) the rules database under the app data
+    /// dir and runs any pending migrations. Called once from `setup`.
+    pub fn initialize(&self, app_handle: &AppHandle<Wry>) -> Result<(), Box<dyn std::error::Error>> {
+        let dir = app_handle.path().app_data_dir()?;
+        std::fs::create_dir_all(&dir)?;
+        let db_path = dir.join("dotdash.sqlite3");
+        let conn = Connection::open(db_path)?;
+        Self::migrate(&conn)?;
+        *self.conn.lock().unwrap() = Some(conn);
+        Ok(())
+    }
+
+    fn migrate(conn: &Connection) -> rusqlite::Result<()> {
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version < 1 {
+            conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS rules (
+                    id INTEGER PRIMARY KEY,
+                    command TEXT UNIQUE NOT NULL,
+                    replacement TEXT NOT NULL,
+                    enabled INTEGER NOT NULL DEFAULT 1,
+                    created_at TEXT NOT NULL
+                );
+                PRAGMA user_version = 1;",
+            )?;
+        }
+        Ok(())
+    }
+
+    fn with_conn<T>(&self, f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+        let guard = self.conn.lock().unwrap();
+        let conn = guard.as_ref().ok_or_else(|| "rule store not initialized".to_string())?;
+        f(conn).map_err(|e| e.to_string())
+    }
+
+    pub fn list(&self) -> Result<Vec<RuleRecord>, String> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, command, replacement, enabled, created_at FROM rules ORDER BY id",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok(RuleRecord {
+                    id: row.get(0)?,
+                    command: row.get(1)?,
+                    replacement: row.get(2)?,
+                    enabled: row.get::<_, i64>(3)? != 0,
+                    created_at: row.get(4)?,
+                })
+            })?;
+            rows.collect()
+        })
+    }
+
+    pub fn add(&self, command: &str, replacement: &str) -> Result<RuleRecord, String> {
+        self.with_conn(|conn| {
+            let created_at = chrono::Utc::now().to_rfc3339();
+            conn.execute(
+                "INSERT INTO rules (command, replacement, enabled, created_at) VALUES (?1, ?2, 1, ?3)",
+                params![command, replacement, created_at],
+            )?;
+            let id = conn.last_insert_rowid();
+            Ok(RuleRecord { id, command: command.to_string(), replacement: replacement.to_string(), enabled: true, created_at })
+        })
+    }
+
+    pub fn update(&self, id: i64, command: &str, replacement: &str, enabled: bool) -> Result<(), String> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE rules SET command = ?1, replacement = ?2, enabled = ?3 WHERE id = ?4",
+                params![command, replacement, enabled as i64, id],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn delete(&self, id: i64) -> Result<(), String> {
+        self.with_conn(|conn| {
+            conn.execute("DELETE FROM rules WHERE id = ?1", params![id])?;
+            Ok(())
+        })
+    }
+}
+
+impl Default for RuleStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,291 @@
+// Platform-agnostic expansion state machine. Everything here is free of
+// CGEventTap/enigo so it can be driven by a real keyboard tap or by a
+// scripted in-memory harness (`TestIo`) with identical matching behavior.
+use crate::matcher::TriggerMatcher;
+use crate::RuleDto;
+use std::sync::Arc;
+
+const BUFFER_CAP: usize = 128;
+
+/// Input/output seam between the matching state machine and whatever is
+/// producing keystrokes and consuming the resulting edits.
+pub trait ExpansionIo {
+    /// Returns the next committed character, or `None` when the stream ends.
+    fn next_char(&mut self) -> Option<String>;
+    fn backspace(&mut self, n: usize);
+    fn type_text(&mut self, s: &str);
+    /// Moves the caret left by `n` characters, used to land on a
+    /// `{{cursor}}` marker after typing a replacement.
+    fn move_cursor_left(&mut self, n: usize);
+}
+
+/// A resolved replacement: the text to type, plus the char offset (from
+/// the start of `text`) a `{{cursor}}` marker landed at, if any.
+pub struct Replacement {
+    pub text: String,
+    pub cursor_offset: Option<usize>,
+}
+
+/// Collaborators `EngineCore` needs but doesn't own: a snapshot of the
+/// current trigger automaton, pause gating, replacement evaluation (static
+/// text, templated tokens, or Lua), and a match notification hook
+/// (logging, event emission). Passed in so the core itself stays free of
+/// engine-global/Tauri state.
+pub struct EngineCoreDeps<'a> {
+    pub matcher: &'a dyn Fn() -> Arc<TriggerMatcher>,
+    pub is_paused: &'a dyn Fn() -> bool,
+    pub eval_replacement: &'a dyn Fn(&RuleDto) -> Option<Replacement>,
+    pub on_match: &'a dyn Fn(&RuleDto, Option<&Replacement>),
+}
+
+/// Runs the buffer-update + rule-matching loop until `io` is exhausted.
+/// Mirrors the semantics the CGEventTap callback used to implement inline:
+/// a rolling 128-char buffer, backspace (`\u{8}`) editing, a delimiter
+/// required immediately after a trigger (now checked via an Aho-Corasick
+/// automaton instead of a linear scan over every rule), and pause gating
+/// that simply drops keystrokes rather than buffering them.
+pub fn engine_core(io: &mut dyn ExpansionIo, deps: &EngineCoreDeps) {
+    let mut buffer: Vec<char> = Vec::new();
+    let mut state = TriggerMatcher::root();
+    let mut matcher = (deps.matcher)();
+
+    while let Some(ch_str) = io.next_char() {
+        if (deps.is_paused)() {
+            continue;
+        }
+
+        // Only pick up a rebuilt automaton at the start of a fresh
+        // buffer/state lifetime. `state` is a raw node index that's only
+        // valid against the specific `TriggerMatcher` it was computed
+        // from (node ids are reassigned from scratch on every rebuild), so
+        // swapping matchers mid-buffer would hand a stale index to the new
+        // automaton — out of bounds at worst, a wrong match at best.
+        if buffer.is_empty() {
+            matcher = (deps.matcher)();
+        }
+
+        if ch_str == "\u{8}" {
+            buffer.pop();
+            state = replay(&matcher, &buffer);
+            continue;
+        }
+
+        let ch = match ch_str.chars().next() {
+            Some(c) => c,
+            None => continue,
+        };
+
+        let is_delim = ch == ' ' || ch == '\n' || ch == '\t';
+
+        if is_delim {
+            if let Some((rule, trigger_len)) = matcher.longest_match(state) {
+                let boundary_ok = buffer.len() == trigger_len
+                    || !buffer[buffer.len() - trigger_len - 1].is_alphanumeric();
+                if boundary_ok {
+                    let mut backspaces = trigger_len;
+                    backspaces += 1; // include delimiter
+                    let replacement = (deps.eval_replacement)(rule);
+                    (deps.on_match)(rule, replacement.as_ref());
+
+                    io.backspace(backspaces);
+                    // Always call type_text, even with no replacement, so an
+                    // IO implementation has one place to reliably reset
+                    // injection state regardless of whether evaluation
+                    // produced anything.
+                    io.type_text(replacement.as_ref().map(|r| r.text.as_str()).unwrap_or(""));
+                    if let Some(Replacement { text, cursor_offset: Some(offset) }) = &replacement {
+                        let total = text.chars().count();
+                        if total > *offset {
+                            io.move_cursor_left(total - offset);
+                        }
+                    }
+
+                    buffer.clear();
+                    state = TriggerMatcher::root();
+                    continue;
+                }
+            }
+        }
+
+        buffer.push(ch);
+        if buffer.len() > BUFFER_CAP {
+            buffer.remove(0);
+            state = replay(&matcher, &buffer);
+        } else {
+            state = matcher.step(state, ch);
+        }
+    }
+}
+
+/// Recomputes the automaton state for `buffer` from `ROOT`, needed
+/// whenever `buffer` is edited out from under the incrementally-stepped
+/// `state` (a backspace, or the 128-char cap dropping the oldest char)
+/// rather than grown by a single keystroke.
+fn replay(matcher: &TriggerMatcher, buffer: &[char]) -> usize {
+    let mut state = TriggerMatcher::root();
+    for &ch in buffer {
+        state = matcher.step(state, ch);
+    }
+    state
+}
+
+/// In-memory `ExpansionIo` for headless tests: feeds a scripted keystroke
+/// sequence and records the backspaces/typed text `engine_core` emits.
+pub struct TestIo {
+    pending: std::collections::VecDeque<String>,
+    pub backspaces: Vec<usize>,
+    pub typed: Vec<String>,
+    pub cursor_moves: Vec<usize>,
+}
+
+impl TestIo {
+    pub fn new<I, S>(keystrokes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            pending: keystrokes.into_iter().map(Into::into).collect(),
+            backspaces: Vec::new(),
+            typed: Vec::new(),
+            cursor_moves: Vec::new(),
+        }
+    }
+}
+
+impl ExpansionIo for TestIo {
+    fn next_char(&mut self) -> Option<String> {
+        self.pending.pop_front()
+    }
+
+    fn backspace(&mut self, n: usize) {
+        self.backspaces.push(n);
+    }
+
+    fn type_text(&mut self, s: &str) {
+        self.typed.push(s.to_string());
+    }
+
+    fn move_cursor_left(&mut self, n: usize) {
+        self.cursor_moves.push(n);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RuleDto;
+    use std::cell::RefCell;
+
+    fn rule(command: &str, replacement: &str) -> RuleDto {
+        RuleDto {
+            id: None,
+            command: command.to_string(),
+            replacementText: replacement.to_string(),
+            enabled: true,
+            script: None,
+        }
+    }
+
+    /// Drives `engine_core` over `keystrokes` against `rules`, returning the
+    /// `TestIo` (backspaces/typed/cursor_moves) and the commands that
+    /// `on_match` fired for, in order.
+    fn run(rules: &[RuleDto], keystrokes: &str, paused: bool) -> (TestIo, Vec<String>) {
+        let matcher = Arc::new(TriggerMatcher::build(rules));
+        let matcher_fn = || matcher.clone();
+        let is_paused_fn = || paused;
+        let eval_replacement_fn = |rule: &RuleDto| -> Option<Replacement> {
+            let (text, cursor_offset) = crate::templates::expand(&rule.replacementText);
+            Some(Replacement { text, cursor_offset })
+        };
+        let matched: RefCell<Vec<String>> = RefCell::new(Vec::new());
+        let on_match_fn = |rule: &RuleDto, _replacement: Option<&Replacement>| {
+            matched.borrow_mut().push(rule.command.clone());
+        };
+        let deps = EngineCoreDeps {
+            matcher: &matcher_fn,
+            is_paused: &is_paused_fn,
+            eval_replacement: &eval_replacement_fn,
+            on_match: &on_match_fn,
+        };
+
+        let mut io = TestIo::new(keystrokes.chars().map(|c| c.to_string()));
+        engine_core(&mut io, &deps);
+        (io, matched.into_inner())
+    }
+
+    #[test]
+    fn matches_a_trigger_followed_by_a_delimiter() {
+        let rules = [rule("brb", "be right back")];
+        let (io, matched) = run(&rules, "brb ", false);
+
+        assert_eq!(matched, vec!["brb"]);
+        assert_eq!(io.backspaces, vec![4]); // 3-char trigger + the delimiter
+        assert_eq!(io.typed, vec!["be right back"]);
+    }
+
+    #[test]
+    fn does_not_match_mid_word() {
+        let rules = [rule("brb", "be right back")];
+        let (_, matched) = run(&rules, "xbrb ", false);
+
+        assert!(matched.is_empty());
+    }
+
+    #[test]
+    fn matches_after_a_non_alphanumeric_boundary() {
+        let rules = [rule("brb", "be right back")];
+        let (_, matched) = run(&rules, "-brb ", false);
+
+        assert_eq!(matched, vec!["brb"]);
+    }
+
+    #[test]
+    fn backspace_edit_still_lets_the_trigger_match() {
+        // Type "pl", backspace the "l", retype "l", then finish "z ". The
+        // buffer ends up holding "plz " exactly as if typed cleanly, so the
+        // trigger should still fire.
+        let rules = [rule("plz", "please")];
+        let (io, matched) = run(&rules, "pl\u{8}lz ", false);
+
+        assert_eq!(matched, vec!["plz"]);
+        assert_eq!(io.typed, vec!["please"]);
+    }
+
+    #[test]
+    fn cap_truncation_preserves_trigger_progress_across_the_boundary() {
+        // Push enough filler that the trigger's later characters land
+        // right as the rolling 128-char buffer starts dropping its
+        // oldest entries, so the automaton state can only be correct if
+        // it's recomputed from the retained buffer rather than reset.
+        let trigger = "abcdefgh";
+        let filler = "x".repeat(BUFFER_CAP - trigger.len());
+        let rules = [rule(trigger, "found it")];
+        let keystrokes = format!("{} {} ", filler, trigger);
+
+        let (io, matched) = run(&rules, &keystrokes, false);
+
+        assert_eq!(matched, vec![trigger]);
+        assert_eq!(io.typed, vec!["found it"]);
+    }
+
+    #[test]
+    fn paused_keystrokes_are_dropped_entirely() {
+        let rules = [rule("brb", "be right back")];
+        let (io, matched) = run(&rules, "brb ", true);
+
+        assert!(matched.is_empty());
+        assert!(io.backspaces.is_empty());
+        assert!(io.typed.is_empty());
+    }
+
+    #[test]
+    fn cursor_token_moves_the_caret_back_after_typing() {
+        let rules = [rule("hi", "Hello {{cursor}}!")];
+        let (io, matched) = run(&rules, "hi ", false);
+
+        assert_eq!(matched, vec!["hi"]);
+        assert_eq!(io.typed, vec!["Hello !"]);
+        assert_eq!(io.cursor_moves, vec![1]); // caret lands before the "!"
+    }
+}
@@ -1,5 +1,5 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
-use tauri::{State, Manager};
+use tauri::{AppHandle, Emitter, State, Manager, Wry};
 use std::sync::{Mutex, Arc};
 use std::thread;
 use std::time::Duration;
@@ -8,9 +8,21 @@ use log::{info, debug, warn, error};
 mod tray;
 use tray::{TrayManager, handle_tray_menu_event};
 
+mod db;
+use db::RuleStore;
+
+mod matcher;
+use matcher::TriggerMatcher;
+
+mod engine;
+use engine::{engine_core, EngineCoreDeps, ExpansionIo, Replacement};
+
+mod templates;
+
 use serde::{Deserialize, Serialize};
 use once_cell::sync::OnceCell;
 use enigo::KeyboardControllable;
+use mlua::Lua;
 use std::sync::atomic::{AtomicBool, Ordering, AtomicUsize};
 use std::ffi::c_void;
 use core_foundation::runloop::{CFRunLoopAddSource, CFRunLoopGetCurrent, CFRunLoopRun, kCFRunLoopDefaultMode};
@@ -74,10 +86,30 @@ fn prompt_accessibility(state: State<AppLogState>) -> bool {
 
 // ===== Expansion engine state =====
 
+fn default_enabled() -> bool { true }
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct RuleDto {
+    #[serde(default)]
+    id: Option<i64>,
     command: String,
     replacementText: String,
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+    #[serde(default)]
+    script: Option<String>,
+}
+
+impl From<db::RuleRecord> for RuleDto {
+    fn from(r: db::RuleRecord) -> Self {
+        Self {
+            id: Some(r.id),
+            command: r.command,
+            replacementText: r.replacement,
+            enabled: r.enabled,
+            script: None,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -91,6 +123,45 @@ struct EngineState {
     // Pause functionality
     paused_by_user: Arc<AtomicBool>,
     paused_by_secure_input: Arc<AtomicBool>,
+    // Set once during app setup so engine-internal state changes (pause,
+    // matches, heartbeats) can push events to the frontend.
+    app_handle: Arc<Mutex<Option<AppHandle<Wry>>>>,
+    // Aho-Corasick automaton over enabled rule triggers, rebuilt whenever
+    // `rules` changes. Wrapped in an outer `Arc` so the hot typing path can
+    // grab a cheap snapshot without holding a lock while it matches.
+    matcher: Arc<Mutex<Arc<TriggerMatcher>>>,
+    // Ring buffer of the last few triggered abbreviations, surfaced in the
+    // tray's "Recent Expansions" section.
+    recent_expansions: Arc<Mutex<std::collections::VecDeque<RecentExpansion>>>,
+    // Expansions performed today; reset whenever `today` rolls over.
+    today_count: Arc<AtomicUsize>,
+    today: Arc<Mutex<String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecentExpansion {
+    pub trigger: String,
+    pub preview: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct MatchEventPayload {
+    trigger: String,
+    replacement_len: usize,
+    dry_run: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct KeycountEventPayload {
+    event_count: usize,
+    running: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct PauseChangedEventPayload {
+    is_paused: bool,
+    paused_by_user: bool,
+    paused_by_secure_input: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -121,11 +192,79 @@ impl Default for EngineState {
             event_count: Arc::new(AtomicUsize::new(0)),
             paused_by_user: Arc::new(AtomicBool::new(false)),
             paused_by_secure_input: Arc::new(AtomicBool::new(false)),
+            app_handle: Arc::new(Mutex::new(None)),
+            matcher: Arc::new(Mutex::new(Arc::new(TriggerMatcher::build(&[])))),
+            recent_expansions: Arc::new(Mutex::new(std::collections::VecDeque::new())),
+            today_count: Arc::new(AtomicUsize::new(0)),
+            today: Arc::new(Mutex::new(String::new())),
         }
     }
 }
 
 impl EngineState {
+    pub fn set_app_handle(&self, app_handle: AppHandle<Wry>) {
+        *self.app_handle.lock().unwrap() = Some(app_handle);
+    }
+
+    /// Records a triggered abbreviation in the recent-expansions ring
+    /// buffer and bumps today's counter, rolling the counter over to 0 if
+    /// the calendar day has changed since the last expansion.
+    pub fn record_expansion(&self, trigger: &str, replacement: &str) {
+        let today = chrono::Local::now().date_naive().to_string();
+        {
+            let mut last_day = self.today.lock().unwrap();
+            if *last_day != today {
+                *last_day = today;
+                self.today_count.store(0, Ordering::SeqCst);
+            }
+        }
+        self.today_count.fetch_add(1, Ordering::SeqCst);
+
+        let preview: String = replacement.chars().take(40).collect();
+        let mut recent = self.recent_expansions.lock().unwrap();
+        recent.push_front(RecentExpansion { trigger: trigger.to_string(), preview });
+        recent.truncate(tray::RECENT_EXPANSIONS_CAP);
+    }
+
+    pub fn recent_expansions(&self) -> Vec<RecentExpansion> {
+        self.recent_expansions.lock().unwrap().iter().cloned().collect()
+    }
+
+    pub fn today_expansion_count(&self) -> usize {
+        self.today_count.load(Ordering::SeqCst)
+    }
+
+    pub fn clear_recent_expansions(&self) {
+        self.recent_expansions.lock().unwrap().clear();
+    }
+
+    /// Rebuilds the trigger automaton from the current in-memory rule
+    /// list. Must be called after any write to `self.rules`.
+    pub fn rebuild_matcher(&self) {
+        let rules = self.rules.lock().unwrap().clone();
+        *self.matcher.lock().unwrap() = Arc::new(TriggerMatcher::build(&rules));
+    }
+
+    pub fn matcher_snapshot(&self) -> Arc<TriggerMatcher> {
+        self.matcher.lock().unwrap().clone()
+    }
+
+    fn emit_event<S: Serialize + Clone>(&self, event: &str, payload: S) {
+        if let Some(app_handle) = self.app_handle.lock().unwrap().as_ref() {
+            if let Err(e) = app_handle.emit(event, payload) {
+                warn!("Failed to emit {}: {}", event, e);
+            }
+        }
+    }
+
+    fn emit_pause_changed(&self) {
+        self.emit_event("engine://pause-changed", PauseChangedEventPayload {
+            is_paused: self.is_paused(),
+            paused_by_user: self.paused_by_user.load(Ordering::SeqCst),
+            paused_by_secure_input: self.paused_by_secure_input.load(Ordering::SeqCst),
+        });
+    }
+
     pub fn pause_expansions(&self, by_user: bool) {
         if by_user {
             self.paused_by_user.store(true, Ordering::SeqCst);
@@ -134,6 +273,7 @@ impl EngineState {
             self.paused_by_secure_input.store(true, Ordering::SeqCst);
             info!("Expansions paused by secure input detection");
         }
+        self.emit_pause_changed();
     }
 
     pub fn resume_expansions(&self, by_user: bool) {
@@ -144,6 +284,7 @@ impl EngineState {
             self.paused_by_secure_input.store(false, Ordering::SeqCst);
             info!("Expansions resumed after secure input ended");
         }
+        self.emit_pause_changed();
     }
 
     pub fn is_paused(&self) -> bool {
@@ -204,9 +345,58 @@ fn set_rules(state: State<AppLogState>, rules: Vec<RuleDto>) {
         let mut w = engine.rules.lock().unwrap();
         *w = rules;
     }
+    engine.rebuild_matcher();
     log_line(&state, &format!("set_rules: updated rules in engine ({} rules)", get_engine().rules.lock().map(|r| r.len()).unwrap_or(0)));
 }
 
+/// Replaces the DB-backed portion of the in-memory rule cache the tap
+/// callback reads, without touching persistence. Used after a CRUD
+/// mutation so the hot path picks up the change immediately. Lua-scripted
+/// rules (`set_rules`) have no DB row, so they're preserved across the
+/// refresh rather than dropped along with the stale DB-backed rules.
+fn refresh_engine_rules(store: &RuleStore) -> Result<Vec<RuleDto>, String> {
+    let records = store.list()?;
+    let mut rules: Vec<RuleDto> = records.into_iter().map(RuleDto::from).collect();
+    let scripted: Vec<RuleDto> = get_engine().rules.lock().unwrap()
+        .iter()
+        .filter(|r| r.script.is_some())
+        .cloned()
+        .collect();
+    rules.extend(scripted);
+    *get_engine().rules.lock().unwrap() = rules.clone();
+    get_engine().rebuild_matcher();
+    Ok(rules)
+}
+
+#[tauri::command]
+fn list_rules(store: State<RuleStore>) -> Result<Vec<RuleDto>, String> {
+    store.list().map(|records| records.into_iter().map(RuleDto::from).collect())
+}
+
+#[tauri::command]
+fn add_rule(state: State<AppLogState>, store: State<RuleStore>, command: String, replacement: String) -> Result<RuleDto, String> {
+    let record = store.add(&command, &replacement)?;
+    refresh_engine_rules(&store)?;
+    log_line(&state, &format!("add_rule: added '{}'", command));
+    Ok(record.into())
+}
+
+#[tauri::command]
+fn update_rule(state: State<AppLogState>, store: State<RuleStore>, id: i64, command: String, replacement: String, enabled: bool) -> Result<(), String> {
+    store.update(id, &command, &replacement, enabled)?;
+    refresh_engine_rules(&store)?;
+    log_line(&state, &format!("update_rule: updated rule {}", id));
+    Ok(())
+}
+
+#[tauri::command]
+fn delete_rule(state: State<AppLogState>, store: State<RuleStore>, id: i64) -> Result<(), String> {
+    store.delete(id)?;
+    refresh_engine_rules(&store)?;
+    log_line(&state, &format!("delete_rule: deleted rule {}", id));
+    Ok(())
+}
+
 #[tauri::command]
 fn start_engine(state: State<AppLogState>, verbose: Option<bool>) -> bool {
     let engine = get_engine().clone();
@@ -222,15 +412,17 @@ fn start_engine(state: State<AppLogState>, verbose: Option<bool>) -> bool {
     thread::spawn(move || {
         push_log(&log_entries, "engine: starting key listener thread (CGEventTap)");
 
-        // Rolling buffer stored behind Arc for callback
-        let buffer = Arc::new(Mutex::new(String::new()));
+        // The tap callback only extracts characters and forwards them;
+        // everything platform-independent (buffering, matching, Lua eval)
+        // runs on this thread via `engine_core`, fed through a channel so
+        // the realtime callback never blocks on Lua or logging.
+        let (char_tx, char_rx) = std::sync::mpsc::channel::<String>();
 
-        // Prepare callback state
         #[repr(C)]
         struct CallbackState {
             engine: EngineState,
             logs: Arc<Mutex<Vec<String>>>,
-            buffer: Arc<Mutex<String>>,
+            sender: std::sync::mpsc::Sender<String>,
         }
 
         extern "C" fn tap_callback(
@@ -245,9 +437,9 @@ fn start_engine(state: State<AppLogState>, verbose: Option<bool>) -> bool {
             if !state.engine.running.load(Ordering::SeqCst) { return event; }
             if state.engine.injecting.load(Ordering::SeqCst) { return event; }
             // Fast pause check - avoid method call overhead
-            if state.engine.paused_by_user.load(Ordering::SeqCst) || 
-               state.engine.paused_by_secure_input.load(Ordering::SeqCst) { 
-                return event; 
+            if state.engine.paused_by_user.load(Ordering::SeqCst) ||
+               state.engine.paused_by_secure_input.load(Ordering::SeqCst) {
+                return event;
             }
 
             // Extract unicode from event
@@ -269,43 +461,131 @@ fn start_engine(state: State<AppLogState>, verbose: Option<bool>) -> bool {
                 push_log(&state.logs, &format!("engine: key ch='{}'", ch));
             }
 
-            // Update buffer and try match
-            if let Ok(mut b) = state.buffer.lock() {
-                if ch == "\u{8}" { let _ = b.pop(); return event; }
-                b.push_str(&ch);
-                if b.len() > 128 { let drain_to = b.len() - 128; b.drain(..drain_to); }
-
-                let is_delim = ch == " " || ch == "\n" || ch == "\t";
-                if is_delim {
-                    let rules = state.engine.rules.lock().unwrap().clone();
-                    for rule in rules {
-                        // Require delimiter after trigger to avoid partial matches
-                        if b.ends_with(&(rule.command.clone() + &ch)) {
-                            push_log(&state.logs, &format!("engine: matched rule '{}'", rule.command));
-                            let mut backspaces = rule.command.chars().count();
-                            backspaces += 1; // include delimiter
-                            if state.engine.dry_run.load(Ordering::SeqCst) {
-                                push_log(&state.logs, &format!(
-                                    "engine: DRY-RUN would delete {} and type {} chars",
-                                    backspaces,
-                                    rule.replacementText.chars().count()
-                                ));
-                            } else {
-                                state.engine.injecting.store(true, Ordering::SeqCst);
-                                let mut en = enigo::Enigo::new();
-                                for _ in 0..backspaces { let _ = en.key_click(enigo::Key::Backspace); }
-                                en.key_sequence(&rule.replacementText);
-                                std::thread::sleep(Duration::from_millis(10));
-                                state.engine.injecting.store(false, Ordering::SeqCst);
+            let _ = state.sender.send(ch);
+            event
+        }
+
+        // Evaluates a scripted rule's replacement text, exposing a small
+        // `trigger`/`matched_text`/`now` context table to the script.
+        fn eval_script(lua: &Lua, script: &str, trigger: &str, matched_text: &str) -> mlua::Result<String> {
+            let ctx = lua.create_table()?;
+            ctx.set("trigger", trigger)?;
+            ctx.set("matched_text", matched_text)?;
+            ctx.set("now", chrono::Utc::now().to_rfc3339())?;
+            lua.globals().set("context", ctx)?;
+            lua.load(script).eval::<String>()
+        }
+
+        // Real `ExpansionIo`: pulls characters off the channel the tap
+        // callback feeds, and turns matches into actual keystrokes via
+        // enigo (or a log line when `dry_run` is set).
+        struct CGEventTapIo {
+            receiver: std::sync::mpsc::Receiver<String>,
+            engine: EngineState,
+        }
+
+        impl ExpansionIo for CGEventTapIo {
+            fn next_char(&mut self) -> Option<String> {
+                self.receiver.recv().ok()
+            }
+
+            fn backspace(&mut self, n: usize) {
+                if self.engine.dry_run.load(Ordering::SeqCst) || n == 0 { return; }
+                self.engine.injecting.store(true, Ordering::SeqCst);
+                let mut en = enigo::Enigo::new();
+                for _ in 0..n { let _ = en.key_click(enigo::Key::Backspace); }
+            }
+
+            fn type_text(&mut self, s: &str) {
+                // Guard so `injecting` is always cleared, even if a future
+                // early return is added to this method. Constructed before
+                // the `dry_run` check so a `dry_run` flip between this
+                // match's `backspace()` and `type_text()` calls still
+                // clears the flag `backspace()` set, instead of leaving it
+                // stuck.
+                struct InjectingGuard<'a>(&'a AtomicBool);
+                impl<'a> Drop for InjectingGuard<'a> {
+                    fn drop(&mut self) { self.0.store(false, Ordering::SeqCst); }
+                }
+                let _guard = InjectingGuard(&self.engine.injecting);
+
+                if self.engine.dry_run.load(Ordering::SeqCst) { return; }
+
+                if !s.is_empty() {
+                    let mut en = enigo::Enigo::new();
+                    en.key_sequence(s);
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+
+            fn move_cursor_left(&mut self, n: usize) {
+                if self.engine.dry_run.load(Ordering::SeqCst) { return; }
+                let mut en = enigo::Enigo::new();
+                for _ in 0..n { let _ = en.key_click(enigo::Key::LeftArrow); }
+            }
+        }
+
+        // Drives `engine_core` on its own thread so Lua evaluation and
+        // logging never block the realtime CGEventTap callback.
+        {
+            let engine = engine.clone();
+            let logs = log_entries.clone();
+            thread::spawn(move || {
+                let lua = Lua::new();
+                let mut io = CGEventTapIo { receiver: char_rx, engine: engine.clone() };
+
+                let matcher_fn = || engine.matcher_snapshot();
+                let is_paused_fn = || engine.is_paused();
+                let eval_replacement_fn = |rule: &RuleDto| -> Option<Replacement> {
+                    match &rule.script {
+                        Some(script) => match eval_script(&lua, script, &rule.command, &rule.command) {
+                            Ok(text) => Some(Replacement { text, cursor_offset: None }),
+                            Err(e) => {
+                                push_log(&logs, &format!("engine: lua error in rule '{}': {}", rule.command, e));
+                                None
                             }
-                            b.clear();
-                            break;
+                        },
+                        None => {
+                            let (text, cursor_offset) = templates::expand(&rule.replacementText);
+                            Some(Replacement { text, cursor_offset })
                         }
                     }
-                }
-            }
+                };
+                let on_match_fn = |rule: &RuleDto, replacement: Option<&Replacement>| {
+                    push_log(&logs, &format!("engine: matched rule '{}'", rule.command));
+                    let dry_run = engine.dry_run.load(Ordering::SeqCst);
+                    engine.emit_event("engine://match", MatchEventPayload {
+                        trigger: rule.command.clone(),
+                        replacement_len: replacement.map(|r| r.text.chars().count()).unwrap_or(0),
+                        dry_run,
+                    });
+
+                    let preview = replacement.map(|r| r.text.as_str()).unwrap_or("");
+                    engine.record_expansion(&rule.command, preview);
+                    if let Some(app_handle) = engine.app_handle.lock().unwrap().as_ref() {
+                        app_handle.state::<TrayManager>().refresh_activity_section();
+                    }
 
-            event
+                    if dry_run {
+                        match replacement {
+                            Some(r) => push_log(&logs, &format!(
+                                "engine: DRY-RUN would type {} chars (cursor offset {:?}): {}",
+                                r.text.chars().count(), r.cursor_offset, r.text
+                            )),
+                            None => push_log(&logs, "engine: DRY-RUN script produced no output"),
+                        }
+                    }
+                };
+
+                let deps = EngineCoreDeps {
+                    matcher: &matcher_fn,
+                    is_paused: &is_paused_fn,
+                    eval_replacement: &eval_replacement_fn,
+                    on_match: &on_match_fn,
+                };
+
+                engine_core(&mut io, &deps);
+            });
         }
 
         unsafe {
@@ -321,7 +601,11 @@ fn start_engine(state: State<AppLogState>, verbose: Option<bool>) -> bool {
             }
 
             // Create one tap: try Session first; if it fails, fall back to HID
-            let state_ptr = Box::into_raw(Box::new(CallbackState { engine: engine.clone(), logs: log_entries.clone(), buffer: buffer.clone() })) as *mut c_void;
+            let state_ptr = Box::into_raw(Box::new(CallbackState {
+                engine: engine.clone(),
+                logs: log_entries.clone(),
+                sender: char_tx.clone(),
+            })) as *mut c_void;
             let mut chosen_tap: *mut c_void = std::ptr::null_mut();
             let tap_session = CGEventTapCreate(1, 0, 1, 1u64 << 10, tap_callback, state_ptr);
             if tap_session.is_null() {
@@ -352,7 +636,9 @@ fn start_engine(state: State<AppLogState>, verbose: Option<bool>) -> bool {
                 thread::spawn(move || {
                     for _ in 0..10 {
                         let n = engine_clone.event_count.load(Ordering::SeqCst);
-                        push_log(&logs, &format!("engine: heartbeat events={} running={}", n, engine_clone.running.load(Ordering::SeqCst)));
+                        let running = engine_clone.running.load(Ordering::SeqCst);
+                        push_log(&logs, &format!("engine: heartbeat events={} running={}", n, running));
+                        engine_clone.emit_event("engine://keycount", KeycountEventPayload { event_count: n, running });
                         thread::sleep(Duration::from_secs(3));
                     }
                 });
@@ -381,10 +667,17 @@ fn set_engine_options(state: State<AppLogState>, verbose: Option<bool>, dry_run:
 #[tauri::command]
 fn inject_text_now(state: State<AppLogState>, text: String) {
     let engine = get_engine();
+    let (expanded, cursor_offset) = templates::expand(&text);
     engine.injecting.store(true, Ordering::SeqCst);
     let mut enigo = enigo::Enigo::new();
-    push_log(&state.entries, &format!("inject_text_now: typing {} chars", text.len()));
-    enigo.key_sequence(&text);
+    push_log(&state.entries, &format!("inject_text_now: typing {} chars", expanded.chars().count()));
+    enigo.key_sequence(&expanded);
+    if let Some(offset) = cursor_offset {
+        let total = expanded.chars().count();
+        if total > offset {
+            for _ in 0..(total - offset) { let _ = enigo.key_click(enigo::Key::LeftArrow); }
+        }
+    }
     thread::sleep(Duration::from_millis(10));
     engine.injecting.store(false, Ordering::SeqCst);
 }
@@ -397,7 +690,8 @@ fn toggle_global_pause(
     let engine = get_engine();
     let currently_paused = engine.paused_by_user.load(Ordering::SeqCst);
     let new_state = !currently_paused;
-    
+
+    tray_manager.cancel_pause_timer();
     if new_state {
         engine.pause_expansions(true);
         tray_manager.update_icon_state(crate::tray::TrayIconState::Paused);
@@ -407,7 +701,8 @@ fn toggle_global_pause(
         tray_manager.update_icon_state(crate::tray::TrayIconState::Active);
         push_log(&state.entries, "Global pause: Expansions resumed by user");
     }
-    
+    tray_manager.update_pause_state();
+
     Ok(new_state)
 }
 
@@ -434,7 +729,8 @@ fn set_pause_state(
 ) -> Result<(), String> {
     let engine = get_engine();
     let is_user_action = by_user.unwrap_or(true);
-    
+
+    tray_manager.cancel_pause_timer();
     if paused {
         engine.pause_expansions(is_user_action);
         tray_manager.update_icon_state(crate::tray::TrayIconState::Paused);
@@ -449,7 +745,8 @@ fn set_pause_state(
         let reason = if is_user_action { "user" } else { "secure input ended" };
         push_log(&state.entries, &format!("set_pause_state: Resumed by {}", reason));
     }
-    
+    tray_manager.update_pause_state();
+
     Ok(())
 }
 
@@ -565,7 +862,22 @@ pub fn run() {
         .plugin(tauri_plugin_log::Builder::default().level(log::LevelFilter::Debug).build())
         .manage(AppLogState::default())
         .manage(TrayManager::new())
+        .manage(RuleStore::new())
         .setup(|app| {
+            get_engine().set_app_handle(app.handle().clone());
+
+            // Open the rules database and load persisted rules into the
+            // in-memory cache the tap callback reads.
+            let rule_store = app.state::<RuleStore>();
+            if let Err(e) = rule_store.initialize(app.handle()) {
+                error!("Failed to initialize rule store: {}", e);
+            } else {
+                match refresh_engine_rules(&rule_store) {
+                    Ok(rules) => info!("Loaded {} persisted rule(s) from disk", rules.len()),
+                    Err(e) => error!("Failed to load persisted rules: {}", e),
+                }
+            }
+
             // Initialize system tray with fallback
             let tray_manager = app.state::<TrayManager>();
             let tray_initialized = match tray_manager.initialize(app.handle()) {
@@ -606,6 +918,10 @@ pub fn run() {
             check_accessibility,
             prompt_accessibility,
             set_rules,
+            list_rules,
+            add_rule,
+            update_rule,
+            delete_rule,
             start_engine,
             set_engine_options,
             inject_text_now,
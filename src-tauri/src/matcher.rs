@@ -0,0 +1,201 @@
+// Hand-rolled Aho-Corasick automaton over rule trigger strings. Built
+// fresh whenever the rule set changes (see `EngineState::rebuild_matcher`)
+// so the hot typing path never has to scan every rule per keystroke.
+// Triggers are stored as `Vec<char>` rather than bytes so multi-byte
+// Unicode triggers still match correctly.
+use crate::RuleDto;
+use std::collections::{HashMap, VecDeque};
+
+pub const ROOT: usize = 0;
+
+struct Node {
+    goto_: HashMap<char, usize>,
+    fail: usize,
+    // Pattern ids completing at this node, including any inherited
+    // through fail links (so a shorter trigger that is a suffix of a
+    // longer one is still reported here).
+    output: Vec<usize>,
+}
+
+pub struct TriggerMatcher {
+    nodes: Vec<Node>,
+    patterns: Vec<(Vec<char>, RuleDto)>,
+}
+
+impl TriggerMatcher {
+    pub fn root() -> usize {
+        ROOT
+    }
+
+    pub fn build(rules: &[RuleDto]) -> Self {
+        let mut nodes = vec![Node { goto_: HashMap::new(), fail: ROOT, output: Vec::new() }];
+        let mut patterns: Vec<(Vec<char>, RuleDto)> = Vec::new();
+
+        for rule in rules {
+            if !rule.enabled {
+                continue;
+            }
+            let chars: Vec<char> = rule.command.chars().collect();
+            if chars.is_empty() {
+                continue;
+            }
+            let pattern_id = patterns.len();
+
+            let mut current = ROOT;
+            for &ch in &chars {
+                current = match nodes[current].goto_.get(&ch) {
+                    Some(&next) => next,
+                    None => {
+                        nodes.push(Node { goto_: HashMap::new(), fail: ROOT, output: Vec::new() });
+                        let next = nodes.len() - 1;
+                        nodes[current].goto_.insert(ch, next);
+                        next
+                    }
+                };
+            }
+            nodes[current].output.push(pattern_id);
+            patterns.push((chars, rule.clone()));
+        }
+
+        Self::build_failure_links(&mut nodes);
+
+        Self { nodes, patterns }
+    }
+
+    fn build_failure_links(nodes: &mut Vec<Node>) {
+        let mut queue: VecDeque<usize> = VecDeque::new();
+
+        let root_children: Vec<usize> = nodes[ROOT].goto_.values().copied().collect();
+        for child in root_children {
+            nodes[child].fail = ROOT;
+            queue.push_back(child);
+        }
+
+        while let Some(current) = queue.pop_front() {
+            let transitions: Vec<(char, usize)> =
+                nodes[current].goto_.iter().map(|(&c, &n)| (c, n)).collect();
+            for (ch, child) in transitions {
+                let mut fail = nodes[current].fail;
+                while fail != ROOT && !nodes[fail].goto_.contains_key(&ch) {
+                    fail = nodes[fail].fail;
+                }
+                let via_fail = nodes[fail].goto_.get(&ch).copied();
+                let child_fail = match via_fail {
+                    Some(n) if n != child => n,
+                    _ => ROOT,
+                };
+                nodes[child].fail = child_fail;
+                let inherited = nodes[child_fail].output.clone();
+                nodes[child].output.extend(inherited);
+                queue.push_back(child);
+            }
+        }
+    }
+
+    /// Advances `state` by one character, following fail links as needed.
+    pub fn step(&self, state: usize, ch: char) -> usize {
+        let mut s = state;
+        loop {
+            if let Some(&next) = self.nodes[s].goto_.get(&ch) {
+                return next;
+            }
+            if s == ROOT {
+                return ROOT;
+            }
+            s = self.nodes[s].fail;
+        }
+    }
+
+    /// Returns the longest trigger (and its rule) completing at `state`,
+    /// preferring longer matches on ties.
+    pub fn longest_match(&self, state: usize) -> Option<(&RuleDto, usize)> {
+        self.nodes[state]
+            .output
+            .iter()
+            .map(|&id| (&self.patterns[id].1, self.patterns[id].0.len()))
+            .max_by_key(|&(_, len)| len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(command: &str) -> RuleDto {
+        RuleDto {
+            id: None,
+            command: command.to_string(),
+            replacementText: String::new(),
+            enabled: true,
+            script: None,
+        }
+    }
+
+    fn step_all(matcher: &TriggerMatcher, s: &str) -> usize {
+        s.chars().fold(ROOT, |state, ch| matcher.step(state, ch))
+    }
+
+    #[test]
+    fn matches_a_single_trigger() {
+        let matcher = TriggerMatcher::build(&[rule("brb")]);
+        let state = step_all(&matcher, "brb");
+
+        let (matched, len) = matcher.longest_match(state).expect("expected a match");
+        assert_eq!(matched.command, "brb");
+        assert_eq!(len, 3);
+    }
+
+    #[test]
+    fn prefers_the_longest_match_when_triggers_share_a_suffix() {
+        // "she" completing also completes "he" via the fail link; the
+        // longer, more specific trigger should win.
+        let matcher = TriggerMatcher::build(&[rule("he"), rule("she")]);
+        let state = step_all(&matcher, "she");
+
+        let (matched, len) = matcher.longest_match(state).expect("expected a match");
+        assert_eq!(matched.command, "she");
+        assert_eq!(len, 3);
+    }
+
+    #[test]
+    fn shorter_trigger_still_matches_on_its_own() {
+        let matcher = TriggerMatcher::build(&[rule("he"), rule("she")]);
+        let state = step_all(&matcher, "he");
+
+        let (matched, len) = matcher.longest_match(state).expect("expected a match");
+        assert_eq!(matched.command, "he");
+        assert_eq!(len, 2);
+    }
+
+    #[test]
+    fn no_match_for_an_unrelated_prefix() {
+        let matcher = TriggerMatcher::build(&[rule("brb"), rule("omw")]);
+        let state = step_all(&matcher, "br");
+
+        assert!(matcher.longest_match(state).is_none());
+    }
+
+    #[test]
+    fn disabled_rules_are_excluded_from_the_automaton() {
+        let mut disabled = rule("brb");
+        disabled.enabled = false;
+        let matcher = TriggerMatcher::build(&[disabled]);
+        let state = step_all(&matcher, "brb");
+
+        assert!(matcher.longest_match(state).is_none());
+    }
+
+    #[test]
+    fn fail_links_recover_a_match_after_a_false_start() {
+        // Typing "s" then "he" should still land on "he"'s output node via
+        // the fail link, even though "s" alone isn't a prefix of "he".
+        let matcher = TriggerMatcher::build(&[rule("he"), rule("she")]);
+        let state = step_all(&matcher, "she");
+        let (matched, _) = matcher.longest_match(state).expect("expected a match");
+        assert_eq!(matched.command, "she");
+
+        // A trailing character that breaks both triggers falls back to root.
+        let broken = matcher.step(state, 'x');
+        assert_eq!(broken, ROOT);
+    }
+}
@@ -0,0 +1,93 @@
+// Dynamic placeholder tokens for static replacement text: `{{date:<fmt>}}`
+// / `{{time:<fmt>}}` (via `chrono`), `{{clipboard}}` (system pasteboard),
+// and `{{cursor}}`, which marks where the caret should land after typing.
+use chrono::Local;
+
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+const DEFAULT_TIME_FORMAT: &str = "%H:%M:%S";
+
+enum Segment {
+    Literal(String),
+    Date(String),
+    Time(String),
+    Clipboard,
+    Cursor,
+}
+
+fn token_to_segment(token: &str) -> Segment {
+    let token = token.trim();
+    match token {
+        "clipboard" => Segment::Clipboard,
+        "cursor" => Segment::Cursor,
+        "date" => Segment::Date(String::new()),
+        "time" => Segment::Time(String::new()),
+        _ => {
+            if let Some(spec) = token.strip_prefix("date:") {
+                Segment::Date(spec.to_string())
+            } else if let Some(spec) = token.strip_prefix("time:") {
+                Segment::Time(spec.to_string())
+            } else {
+                // Unknown token: keep it literal so a typo in a rule is
+                // visible in the output instead of silently vanishing.
+                Segment::Literal(format!("{{{{{}}}}}", token))
+            }
+        }
+    }
+}
+
+fn parse(template: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            segments.push(Segment::Literal(rest[..start].to_string()));
+        }
+        rest = &rest[start + 2..];
+        match rest.find("}}") {
+            Some(end) => {
+                segments.push(token_to_segment(&rest[..end]));
+                rest = &rest[end + 2..];
+            }
+            None => {
+                segments.push(Segment::Literal(format!("{{{{{}", rest)));
+                rest = "";
+            }
+        }
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Literal(rest.to_string()));
+    }
+    segments
+}
+
+fn read_clipboard() -> String {
+    arboard::Clipboard::new()
+        .and_then(|mut c| c.get_text())
+        .unwrap_or_default()
+}
+
+/// Expands a replacement template into the final string to type, plus the
+/// char offset `{{cursor}}` landed at (if present), measured from the
+/// start of the expanded string.
+pub fn expand(template: &str) -> (String, Option<usize>) {
+    let mut out = String::new();
+    let mut cursor_offset = None;
+
+    for segment in parse(template) {
+        match segment {
+            Segment::Literal(s) => out.push_str(&s),
+            Segment::Date(fmt) => {
+                let fmt = if fmt.is_empty() { DEFAULT_DATE_FORMAT } else { &fmt };
+                out.push_str(&Local::now().format(fmt).to_string());
+            }
+            Segment::Time(fmt) => {
+                let fmt = if fmt.is_empty() { DEFAULT_TIME_FORMAT } else { &fmt };
+                out.push_str(&Local::now().format(fmt).to_string());
+            }
+            Segment::Clipboard => out.push_str(&read_clipboard()),
+            Segment::Cursor => cursor_offset = Some(out.chars().count()),
+        }
+    }
+
+    (out, cursor_offset)
+}
@@ -1,12 +1,21 @@
 use tauri::{
+    image::Image,
     tray::{TrayIcon, TrayIconBuilder, TrayIconEvent},
-    menu::{Menu, MenuItem, PredefinedMenuItem},
+    menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
     AppHandle, Manager, Emitter, Wry,
 };
 use std::sync::{Arc, Mutex};
+use std::thread;
 use std::time::{Duration, Instant};
 use log::{info, error, warn, debug};
 
+const PAUSE_TIMER_TICK: Duration = Duration::from_secs(30);
+
+/// Number of recent-trigger slots shown in the tray's activity section.
+/// Also the size the engine's ring buffer is capped at, since there's no
+/// point keeping more history than the tray can display.
+pub(crate) const RECENT_EXPANSIONS_CAP: usize = 5;
+
 #[derive(Debug, Clone)]
 pub enum TrayIconState {
     Active,
@@ -15,9 +24,60 @@ pub enum TrayIconState {
     Error,
 }
 
+/// Preloaded tray icon variants, decoded once from embedded bytes so
+/// `update_icon_state` can swap icons with a cheap clone instead of
+/// touching the filesystem on every state change.
+struct IconSet {
+    active: Image<'static>,
+    paused: Image<'static>,
+    warning: Image<'static>,
+    error: Image<'static>,
+}
+
+impl IconSet {
+    fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            active: Image::from_bytes(include_bytes!("../icons/tray-active.png"))?,
+            paused: Image::from_bytes(include_bytes!("../icons/tray-paused.png"))?,
+            warning: Image::from_bytes(include_bytes!("../icons/tray-warning.png"))?,
+            error: Image::from_bytes(include_bytes!("../icons/tray-error.png"))?,
+        })
+    }
+
+    fn for_state(&self, state: &TrayIconState) -> Image<'static> {
+        match state {
+            TrayIconState::Active => self.active.clone(),
+            TrayIconState::Paused => self.paused.clone(),
+            TrayIconState::Warning => self.warning.clone(),
+            TrayIconState::Error => self.error.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct TrayManager {
     tray_icon: Arc<Mutex<Option<TrayIcon<Wry>>>>,
+    icons: Arc<Mutex<Option<IconSet>>>,
+    toggle_item: Arc<Mutex<Option<MenuItem<Wry>>>>,
+    // Set once during `initialize` so methods reached off the builder
+    // closure (tray clicks, Tauri commands) can still get at the app.
+    app_handle: Arc<Mutex<Option<AppHandle<Wry>>>>,
     expansion_enabled: Arc<Mutex<bool>>,
+    // When a "Pause for..." timer is running, the instant it should
+    // auto-resume at. `pause_timer_generation` is bumped every time a
+    // new timer starts or a manual resume happens, so a stale timer
+    // thread notices it's been superseded and exits quietly instead of
+    // firing a resume nobody asked for.
+    paused_until: Arc<Mutex<Option<Instant>>>,
+    pause_timer_generation: Arc<Mutex<u64>>,
+    // "Recent Expansions" activity section: a disabled counter item, a
+    // fixed pool of clickable recent-trigger items (cached so routine
+    // refreshes can mutate them in place instead of rebuilding the menu),
+    // and the expansion data behind each slot for click-to-open lookups.
+    counter_item: Arc<Mutex<Option<MenuItem<Wry>>>>,
+    recent_items: Arc<Mutex<Vec<MenuItem<Wry>>>>,
+    clear_item: Arc<Mutex<Option<MenuItem<Wry>>>>,
+    recent_slots: Arc<Mutex<Vec<Option<crate::RecentExpansion>>>>,
     last_update: Arc<Mutex<Instant>>,
     update_throttle: Duration,
 }
@@ -26,7 +86,16 @@ impl TrayManager {
     pub fn new() -> Self {
         Self {
             tray_icon: Arc::new(Mutex::new(None)),
+            icons: Arc::new(Mutex::new(None)),
+            toggle_item: Arc::new(Mutex::new(None)),
+            app_handle: Arc::new(Mutex::new(None)),
             expansion_enabled: Arc::new(Mutex::new(true)),
+            paused_until: Arc::new(Mutex::new(None)),
+            pause_timer_generation: Arc::new(Mutex::new(0)),
+            counter_item: Arc::new(Mutex::new(None)),
+            recent_items: Arc::new(Mutex::new(Vec::new())),
+            clear_item: Arc::new(Mutex::new(None)),
+            recent_slots: Arc::new(Mutex::new(Vec::new())),
             last_update: Arc::new(Mutex::new(Instant::now())),
             update_throttle: Duration::from_millis(100), // Throttle updates to max 10/second
         }
@@ -35,6 +104,8 @@ impl TrayManager {
     pub fn initialize(&self, app_handle: &AppHandle<Wry>) -> Result<(), Box<dyn std::error::Error>> {
         info!("Initializing system tray");
 
+        *self.app_handle.lock().unwrap() = Some(app_handle.clone());
+
         // Check if system tray is available
         if !self.is_system_tray_available() {
             warn!("System tray is not available on this system");
@@ -50,27 +121,41 @@ impl TrayManager {
             }
         };
 
-        // Get default icon with fallback
-        let icon = match app_handle.default_window_icon() {
-            Some(icon) => icon.clone(),
-            None => {
-                warn!("No default window icon found, using fallback");
-                // In a real implementation, we'd load a fallback icon
-                return Err("No icon available for tray".into());
+        // Decode the embedded icon variants once; fall back to the app's
+        // default window icon if they can't be loaded so the tray still
+        // comes up (just without per-state artwork).
+        let icon = match IconSet::load() {
+            Ok(icons) => {
+                let active = icons.active.clone();
+                *self.icons.lock().unwrap() = Some(icons);
+                active
+            }
+            Err(e) => {
+                warn!("Failed to load tray icon set, falling back to default window icon: {}", e);
+                match app_handle.default_window_icon() {
+                    Some(icon) => icon.clone(),
+                    None => {
+                        warn!("No default window icon found, using fallback");
+                        return Err("No icon available for tray".into());
+                    }
+                }
             }
         };
 
-        // Create tray icon with comprehensive error handling
-        let tray_icon = match TrayIconBuilder::new()
+        let mut builder = TrayIconBuilder::new()
             .icon(icon)
             .menu(&menu)
-            .tooltip("DotDash - Text Expander")
-            .on_tray_icon_event(|tray, event| {
-                if let Err(e) = Self::handle_tray_icon_event(tray, event) {
-                    error!("Error handling tray icon event: {}", e);
-                }
-            })
-            .build(app_handle) {
+            .tooltip("DotDash - Text Expander");
+
+        // On macOS the tray glyph should be monochrome and follow the
+        // light/dark menu bar theme rather than render in color.
+        #[cfg(target_os = "macos")]
+        {
+            builder = builder.icon_as_template(true);
+        }
+
+        // Create tray icon with comprehensive error handling
+        let tray_icon = match builder.build(app_handle) {
                 Ok(tray) => tray,
                 Err(e) => {
                     error!("Failed to create tray icon: {}", e);
@@ -84,6 +169,16 @@ impl TrayManager {
             *tray_guard = Some(tray_icon);
         }
 
+        // Dispatch tray icon events through an app-level global listener
+        // rather than the per-builder closure, so click handling keeps
+        // working uniformly even if multiple windows or future trays
+        // are added.
+        app_handle.on_tray_icon_event(|app, event| {
+            if let Err(e) = Self::handle_tray_icon_event(app, event) {
+                error!("Error handling tray icon event: {}", e);
+            }
+        });
+
         info!("System tray initialized successfully");
         Ok(())
     }
@@ -103,7 +198,7 @@ impl TrayManager {
     }
 
     fn handle_tray_icon_event(
-        tray: &TrayIcon<Wry>, 
+        app_handle: &AppHandle<Wry>,
         event: TrayIconEvent
     ) -> Result<(), Box<dyn std::error::Error>> {
         match event {
@@ -111,7 +206,7 @@ impl TrayManager {
                 match button {
                     tauri::tray::MouseButton::Left => {
                         info!("Tray icon left-clicked");
-                        if let Some(window) = tray.app_handle().get_webview_window("main") {
+                        if let Some(window) = app_handle.get_webview_window("main") {
                             window.show().map_err(|e| format!("Failed to show window: {}", e))?;
                             window.set_focus().map_err(|e| format!("Failed to focus window: {}", e))?;
                         } else {
@@ -142,66 +237,151 @@ impl TrayManager {
         // Check the actual pause state from the engine
         let engine = crate::get_engine();
         let is_paused = engine.is_paused();
-        
-        let toggle_text = if is_paused {
-            "Resume Expansions"
-        } else {
-            "Pause Expansions"
-        };
+        let toggle_text = Self::toggle_label(is_paused, *self.paused_until.lock().unwrap());
 
         let toggle_item = MenuItem::with_id(app_handle, "toggle_expansions", toggle_text, true, None::<&str>)?;
+
+        let pause_15m = MenuItem::with_id(app_handle, "pause_for_15m", "15 Minutes", true, None::<&str>)?;
+        let pause_1h = MenuItem::with_id(app_handle, "pause_for_1h", "1 Hour", true, None::<&str>)?;
+        let pause_indefinite = MenuItem::with_id(app_handle, "pause_until_resume", "Until I Resume", true, None::<&str>)?;
+        let pause_for_submenu = Submenu::with_id_and_items(
+            app_handle,
+            "pause_for",
+            "Pause for...",
+            true,
+            &[&pause_15m, &pause_1h, &pause_indefinite],
+        )?;
+
         let separator1 = PredefinedMenuItem::separator(app_handle)?;
+
+        // "Recent Expansions" activity section: a disabled counter line,
+        // a fixed pool of clickable recent-trigger slots (blank/disabled
+        // when there's nothing to show yet), and a "Clear recent" action.
+        let recent = engine.recent_expansions();
+        let counter_item = MenuItem::with_id(
+            app_handle,
+            "activity_counter",
+            Self::counter_label(engine.today_expansion_count()),
+            false,
+            None::<&str>,
+        )?;
+
+        let mut recent_items: Vec<MenuItem<Wry>> = Vec::with_capacity(RECENT_EXPANSIONS_CAP);
+        for i in 0..RECENT_EXPANSIONS_CAP {
+            let (text, enabled) = match recent.get(i) {
+                Some(r) => (Self::recent_label(r), true),
+                None => ("(no recent expansions)".to_string(), false),
+            };
+            recent_items.push(MenuItem::with_id(app_handle, format!("recent_{}", i), text, enabled, None::<&str>)?);
+        }
+
+        let clear_item = MenuItem::with_id(app_handle, "clear_recent", "Clear Recent", !recent.is_empty(), None::<&str>)?;
+        let separator3 = PredefinedMenuItem::separator(app_handle)?;
+
         let open_item = MenuItem::with_id(app_handle, "open_window", "Open DotDash", true, None::<&str>)?;
         let diagnostics_item = MenuItem::with_id(app_handle, "diagnostics", "Diagnostics", true, None::<&str>)?;
         let separator2 = PredefinedMenuItem::separator(app_handle)?;
         let quit_item = MenuItem::with_id(app_handle, "quit", "Quit DotDash", true, None::<&str>)?;
 
-        let menu = Menu::with_items(app_handle, &[
+        let mut menu_items: Vec<&dyn tauri::menu::IsMenuItem<Wry>> = vec![
             &toggle_item,
+            &pause_for_submenu,
             &separator1,
-            &open_item,
-            &diagnostics_item,
-            &separator2,
-            &quit_item,
-        ])?;
+            &counter_item,
+        ];
+        for item in &recent_items {
+            menu_items.push(item);
+        }
+        menu_items.push(&clear_item);
+        menu_items.push(&separator3);
+        menu_items.push(&open_item);
+        menu_items.push(&diagnostics_item);
+        menu_items.push(&separator2);
+        menu_items.push(&quit_item);
+
+        let menu = Menu::with_items(app_handle, &menu_items)?;
+
+        // Cache the item handles so routine refreshes (pause/resume label
+        // flips, new expansions firing) can mutate them in place instead
+        // of rebuilding the whole menu.
+        *self.toggle_item.lock().unwrap() = Some(toggle_item);
+        *self.counter_item.lock().unwrap() = Some(counter_item);
+        *self.clear_item.lock().unwrap() = Some(clear_item);
+        *self.recent_slots.lock().unwrap() = (0..RECENT_EXPANSIONS_CAP).map(|i| recent.get(i).cloned()).collect();
+        *self.recent_items.lock().unwrap() = recent_items;
 
         Ok(menu)
     }
 
-    pub fn update_expansion_state(&self, enabled: bool) {
-        // Check if state actually changed to avoid unnecessary updates
-        let state_changed = {
-            let mut expansion_guard = self.expansion_enabled.lock().unwrap();
-            let old_state = *expansion_guard;
-            *expansion_guard = enabled;
-            old_state != enabled
-        };
+    fn counter_label(count: usize) -> String {
+        format!("Today: {} expansion{}", count, if count == 1 { "" } else { "s" })
+    }
+
+    fn recent_label(expansion: &crate::RecentExpansion) -> String {
+        format!("{} \u{2192} {}", expansion.trigger, expansion.preview)
+    }
 
-        if !state_changed {
-            return; // No change, skip expensive menu update
+    /// Label for the toggle item: plain "Pause/Resume Expansions" unless
+    /// a "Pause for..." timer is running, in which case it counts down
+    /// ("Resume (42m left)").
+    fn toggle_label(is_paused: bool, paused_until: Option<Instant>) -> String {
+        if !is_paused {
+            return "Pause Expansions".to_string();
         }
 
-        // Update tray menu only if state changed
-        if let Some(app_handle) = self.get_app_handle() {
-            if let Err(e) = self.update_menu(&app_handle, enabled) {
-                error!("Failed to update tray menu: {}", e);
+        match paused_until {
+            Some(until) => {
+                let remaining = until.saturating_duration_since(Instant::now());
+                let minutes = (remaining.as_secs() + 59) / 60;
+                format!("Resume ({}m left)", minutes.max(1))
             }
+            None => "Resume Expansions".to_string(),
         }
     }
 
+    /// Tracks the engine's running on/off flag, an entirely different
+    /// concept from pause state. Deliberately does NOT touch the cached
+    /// `toggle_expansions` item — that label belongs to `update_pause_state`
+    /// alone, or toggling the engine off/on here would stomp the real
+    /// pause/"Pause for..." countdown label with the wrong text.
+    pub fn update_expansion_state(&self, enabled: bool) {
+        *self.expansion_enabled.lock().unwrap() = enabled;
+    }
+
     pub fn update_pause_state(&self) {
-        // Update tray menu to reflect current pause state
-        if let Some(app_handle) = self.get_app_handle() {
-            let expansion_enabled = self.expansion_enabled.lock().unwrap();
-            if let Err(e) = self.update_menu(&app_handle, *expansion_enabled) {
-                error!("Failed to update tray menu for pause state: {}", e);
+        // Reflect the engine's actual pause state on the cached toggle
+        // item, not the separate `expansion_enabled` on/off flag.
+        let is_paused = crate::get_engine().is_paused();
+        self.set_toggle_label(is_paused);
+    }
+
+    /// Flip the cached `toggle_expansions` item's label in place. Falls
+    /// back to rebuilding the whole menu if the item hasn't been cached
+    /// yet (e.g. `initialize` hasn't run).
+    fn set_toggle_label(&self, is_paused: bool) {
+        let paused_until = *self.paused_until.lock().unwrap();
+        let toggle_text = Self::toggle_label(is_paused, paused_until);
+
+        let toggle_item = self.toggle_item.lock().unwrap().clone();
+        match toggle_item {
+            Some(item) => {
+                if let Err(e) = item.set_text(toggle_text) {
+                    error!("Failed to update toggle menu item text: {}", e);
+                }
+            }
+            None => {
+                if let Some(app_handle) = self.get_app_handle() {
+                    if let Err(e) = self.update_menu(&app_handle, !is_paused) {
+                        error!("Failed to update tray menu: {}", e);
+                    }
+                }
             }
         }
     }
 
     fn update_menu(&self, app_handle: &AppHandle<Wry>, expansion_enabled: bool) -> Result<(), Box<dyn std::error::Error>> {
         let menu = self.build_menu(app_handle, expansion_enabled)?;
-        
+
         if let Some(tray_icon) = self.tray_icon.lock().unwrap().as_ref() {
             tray_icon.set_menu(Some(menu))?;
         }
@@ -236,21 +416,143 @@ impl TrayManager {
                 warn!("Failed to update tray tooltip: {}", e);
             }
 
-            // TODO: Switch icon based on state
-            // This would require loading different icon files from resources
-            // For now, we just update the tooltip
-            /*
-            let icon_path = match state {
-                TrayIconState::Active => "icons/tray-icon.png",
-                TrayIconState::Paused => "icons/tray-icon-paused.png",
-                TrayIconState::Warning => "icons/tray-icon-warning.png",
-                TrayIconState::Error => "icons/tray-icon-warning.png",
-            };
-            
-            if let Ok(icon) = load_icon(icon_path) {
-                let _ = tray_icon.set_icon(Some(icon));
+            if let Some(icons) = self.icons.lock().unwrap().as_ref() {
+                if let Err(e) = tray_icon.set_icon(Some(icons.for_state(&state))) {
+                    warn!("Failed to update tray icon: {}", e);
+                }
+
+                #[cfg(target_os = "macos")]
+                {
+                    if let Err(e) = tray_icon.set_icon_as_template(true) {
+                        warn!("Failed to keep tray icon in template mode: {}", e);
+                    }
+                }
+            } else {
+                debug!("No icon set loaded, leaving tray icon as-is");
+            }
+        }
+    }
+
+    /// Pause expansions, optionally for a fixed `duration`. When a
+    /// duration is given, spawns a timer thread that auto-resumes once it
+    /// elapses and, in the meantime, keeps the toggle label's countdown
+    /// fresh. A later call to `pause_for` or a manual `resume` bumps
+    /// `pause_timer_generation`, so a superseded timer notices the
+    /// mismatch and exits instead of firing a stale resume.
+    pub fn pause_for(&self, app_handle: &AppHandle<Wry>, duration: Option<Duration>) {
+        let engine = crate::get_engine();
+        engine.pause_expansions(true);
+        self.update_icon_state(TrayIconState::Paused);
+
+        let until = duration.map(|d| Instant::now() + d);
+        *self.paused_until.lock().unwrap() = until;
+        let generation = {
+            let mut gen = self.pause_timer_generation.lock().unwrap();
+            *gen += 1;
+            *gen
+        };
+
+        self.update_pause_state();
+        Self::emit_pause_state_changed(app_handle);
+
+        let Some(until) = until else {
+            info!("Expansions paused indefinitely via tray");
+            return;
+        };
+        info!("Expansions paused via tray for {:?}", duration.unwrap());
+
+        let manager = self.clone();
+        let app_handle = app_handle.clone();
+        thread::spawn(move || {
+            loop {
+                if *manager.pause_timer_generation.lock().unwrap() != generation {
+                    debug!("Pause timer superseded, exiting");
+                    return;
+                }
+
+                let remaining = until.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    break;
+                }
+
+                thread::sleep(remaining.min(PAUSE_TIMER_TICK));
+                manager.update_pause_state();
+            }
+
+            if *manager.pause_timer_generation.lock().unwrap() != generation {
+                return;
+            }
+
+            info!("Pause-for timer elapsed, auto-resuming expansions");
+            *manager.paused_until.lock().unwrap() = None;
+            crate::get_engine().resume_expansions(true);
+            manager.update_icon_state(TrayIconState::Active);
+            manager.update_pause_state();
+            Self::emit_pause_state_changed(&app_handle);
+        });
+    }
+
+    /// Cancel any pending "Pause for..." timer, e.g. because the user
+    /// resumed manually before it elapsed.
+    pub(crate) fn cancel_pause_timer(&self) {
+        *self.paused_until.lock().unwrap() = None;
+        *self.pause_timer_generation.lock().unwrap() += 1;
+    }
+
+    fn emit_pause_state_changed(app_handle: &AppHandle<Wry>) {
+        if let Some(window) = app_handle.get_webview_window("main") {
+            if let Err(e) = window.emit("pause-state-changed", ()) {
+                warn!("Failed to emit pause-state-changed: {}", e);
             }
-            */
+        }
+    }
+
+    /// Refreshes the "Recent Expansions" section (counter + recent-trigger
+    /// slots) in place whenever a new expansion fires, throttled by the
+    /// same clock `update_icon_state` uses so a burst of expansions
+    /// doesn't hammer the tray with menu updates.
+    pub fn refresh_activity_section(&self) {
+        {
+            let mut last_update = self.last_update.lock().unwrap();
+            let now = Instant::now();
+            if now.duration_since(*last_update) < self.update_throttle {
+                debug!("Throttling tray activity refresh");
+                return;
+            }
+            *last_update = now;
+        }
+
+        let engine = crate::get_engine();
+        let recent = engine.recent_expansions();
+
+        if let Some(counter) = self.counter_item.lock().unwrap().as_ref() {
+            if let Err(e) = counter.set_text(Self::counter_label(engine.today_expansion_count())) {
+                warn!("Failed to update activity counter: {}", e);
+            }
+        }
+
+        let items = self.recent_items.lock().unwrap().clone();
+        let mut slots = Vec::with_capacity(RECENT_EXPANSIONS_CAP);
+        for (i, item) in items.iter().enumerate() {
+            match recent.get(i) {
+                Some(r) => {
+                    if let Err(e) = item.set_text(Self::recent_label(r)) {
+                        warn!("Failed to update recent-expansion item: {}", e);
+                    }
+                    let _ = item.set_enabled(true);
+                    slots.push(Some(r.clone()));
+                }
+                None => {
+                    let _ = item.set_text("(no recent expansions)");
+                    let _ = item.set_enabled(false);
+                    slots.push(None);
+                }
+            }
+        }
+        *self.recent_slots.lock().unwrap() = slots;
+
+        if let Some(clear) = self.clear_item.lock().unwrap().as_ref() {
+            let _ = clear.set_enabled(!recent.is_empty());
         }
     }
 
@@ -263,9 +565,7 @@ impl TrayManager {
     }
 
     fn get_app_handle(&self) -> Option<AppHandle<Wry>> {
-        // This is a simplified approach - in a real implementation,
-        // we'd store the app handle or get it through other means
-        None
+        self.app_handle.lock().unwrap().clone()
     }
 
     pub fn show_main_window(&self) -> Result<(), Box<dyn std::error::Error>> {
@@ -308,6 +608,25 @@ pub fn handle_tray_menu_event(app_handle: &AppHandle<Wry>, event_id: &str) {
                 error!("Failed to toggle pause from tray: {}", e);
             }
         }
+        "pause_for_15m" => {
+            app_handle.state::<TrayManager>().pause_for(app_handle, Some(Duration::from_secs(15 * 60)));
+        }
+        "pause_for_1h" => {
+            app_handle.state::<TrayManager>().pause_for(app_handle, Some(Duration::from_secs(60 * 60)));
+        }
+        "pause_until_resume" => {
+            app_handle.state::<TrayManager>().pause_for(app_handle, None);
+        }
+        "clear_recent" => {
+            info!("Clear recent expansions requested from tray");
+            crate::get_engine().clear_recent_expansions();
+            app_handle.state::<TrayManager>().refresh_activity_section();
+        }
+        id if id.starts_with("recent_") => {
+            if let Some(index) = id.strip_prefix("recent_").and_then(|s| s.parse::<usize>().ok()) {
+                open_recent_snippet(app_handle, index);
+            }
+        }
         "open_window" => {
             info!("Open window requested from tray");
             if let Some(window) = app_handle.get_webview_window("main") {
@@ -349,26 +668,50 @@ fn toggle_pause_via_tray(app_handle: &AppHandle<Wry>) -> Result<(), Box<dyn std:
     let new_state = !currently_paused;
     
     if new_state {
+        tray_manager.cancel_pause_timer();
         engine.pause_expansions(true);
         tray_manager.update_icon_state(TrayIconState::Paused);
         info!("Expansions paused via tray");
     } else {
+        tray_manager.cancel_pause_timer();
         engine.resume_expansions(true);
         tray_manager.update_icon_state(TrayIconState::Active);
         info!("Expansions resumed via tray");
     }
-    
+    tray_manager.update_pause_state();
+
     // Emit event to frontend to update UI
     if let Some(window) = app_handle.get_webview_window("main") {
         window.emit("pause-state-changed", ())?;
     }
-    
+
     Ok(())
 }
 
+/// Opens the main window focused on the snippet behind a clicked
+/// "Recent Expansions" entry, mirroring how `diagnostics` navigates the
+/// frontend via an emitted event.
+fn open_recent_snippet(app_handle: &AppHandle<Wry>, index: usize) {
+    let tray_manager = app_handle.state::<TrayManager>();
+    let snippet = tray_manager.recent_slots.lock().unwrap().get(index).cloned().flatten();
+
+    let Some(snippet) = snippet else {
+        warn!("Recent expansion slot {} clicked but no longer has data", index);
+        return;
+    };
+
+    info!("Recent expansion '{}' clicked from tray", snippet.trigger);
+    if let Some(window) = app_handle.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+        let _ = window.unminimize();
+        let _ = window.emit("navigate-to-snippet", snippet.trigger);
+    }
+}
+
 fn cleanup_before_quit(app_handle: &AppHandle<Wry>) {
     info!("Performing cleanup before quit");
-    
+
     // Stop expansion engine if running
     let engine = crate::get_engine();
     if engine.running.load(std::sync::atomic::Ordering::SeqCst) {